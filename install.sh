@@ -20,6 +20,11 @@ NC='\033[0m'
 
 LOG_FILE="${PROJECT_PATH}/install.log"
 
+# Seconds to wait for the apt/dpkg locks before giving up
+APT_LOCK_TIMEOUT=600
+# Makes apt itself wait for the lock instead of failing (apt >= 1.9.11)
+APT_LOCK_OPTION="-o DPkg::Lock::Timeout=$APT_LOCK_TIMEOUT"
+
 log() {
     local log_color="$1"
     local log_message="$2"
@@ -42,6 +47,37 @@ stop_script() {
     exit 1
 }
 
+# project_config.toml holds the JWT secret and the default user password,
+# so it and install.log must be readable by the owner only
+restrict_file_permissions() {
+    local file="$1"
+    local mode
+
+    mode=$(stat -c '%a' "$file") || stop_script "Failed to read permissions of $file"
+    if [ "$mode" != "600" ]; then
+        log $RED "$file has permissions $mode, restricting them to 600"
+        chmod 600 "$file" || stop_script "Failed to restrict permissions of $file"
+    fi
+}
+
+# Read a value from a section of project_config.toml. Quotes around the value
+# and a trailing comment are removed
+get_config_value() {
+    local section="$1"
+    local key="$2"
+    sed -n "/^[[:space:]]*\[${section}\]/,/^[[:space:]]*\[/{s/^[[:space:]]*${key}[[:space:]]*=[[:space:]]*//p}" "$PROJECT_CONFIG_FILE" \
+        | sed -E "s/^'([^']*)'.*/\1/;t;s/^\"([^\"]*)\".*/\1/;t;s/[[:space:]]*(#.*)?$//" \
+        | head -n 1
+}
+
+# Match a value against a regular expression in the C locale, where ranges
+# such as [a-z] contain latin letters only. The subshell keeps the locale
+# change away from the caller
+matches_ascii() (
+    LC_ALL=C
+    [[ $1 =~ $2 ]]
+)
+
 # Function to execute a command
 execute() {
     local command="$1"
@@ -55,43 +91,133 @@ execute() {
     fi
 }
 
-check_or_install() {
+# Wait until no process holds the apt/dpkg locks (e.g. unattended-upgrades).
+# The lock can be taken again right after this check, so apt calls also pass
+# APT_LOCK_OPTION; this loop reports who is holding the lock
+wait_for_apt_lock() {
+    local lock_files="/var/lib/dpkg/lock-frontend /var/lib/dpkg/lock /var/lib/apt/lists/lock"
+    local waited=0
+    local holder
+
+    if ! command -v fuser &> /dev/null; then
+        if [ -z "$FUSER_MISSING_LOGGED" ]; then
+            log $RED "fuser is not installed, apt will wait up to ${APT_LOCK_TIMEOUT}s for its lock without reporting the holder"
+            FUSER_MISSING_LOGGED=1
+        fi
+        return
+    fi
+
+    while holder=$(sudo fuser $lock_files 2>/dev/null | xargs) && [ -n "$holder" ]; do
+        if [ "$waited" -ge "$APT_LOCK_TIMEOUT" ]; then
+            stop_script "Apt lock is still held by PID $holder after ${APT_LOCK_TIMEOUT}s"
+        fi
+        log $CYAN "Waiting for apt lock held by PID $holder..."
+        sleep 5
+        waited=$((waited + 5))
+    done
+}
+
+# Check if a single package is installed according to dpkg
+is_package_installed() {
     local package=$1
-    log $CYAN "Installing $package..."
-    if ! dpkg -l | grep -q $package; then
-        sudo apt-get install -y $package || stop_script "Failed to install $package"
-        log $GREEN "$package installed successfully"
+    dpkg-query -W -f='${Status}' "$package" 2>/dev/null | grep -q "install ok installed"
+}
+
+# Accepts one or several space separated package names
+check_or_install() {
+    local packages=$1
+    local missing_packages=()
+    log $CYAN "Installing $packages..."
+    for package in $packages; do
+        if ! is_package_installed "$package"; then
+            missing_packages+=("$package")
+        fi
+    done
+
+    if [ ${#missing_packages[@]} -gt 0 ]; then
+        wait_for_apt_lock
+        sudo apt-get $APT_LOCK_OPTION install -y "${missing_packages[@]}" || stop_script "Failed to install ${missing_packages[*]}"
+        log $GREEN "$packages installed successfully"
     else
-        log $GREEN "$package is already installed"
+        log $GREEN "$packages is already installed"
     fi
 }
 
+# Generate a random secret of the given number of bytes. The encoding is
+# hex, base64 or base64url (URL-safe alphabet without padding)
 generate_random_secret() {
-    openssl rand -hex 32
+    local secret_bytes=${1:-32}
+    local secret_encoding=${2:-hex}
+
+    if ! [[ $secret_bytes =~ ^[1-9][0-9]*$ ]]; then
+        echo "Secret size must be a positive number of bytes: $secret_bytes" >&2
+        return 1
+    fi
+
+    case "$secret_encoding" in
+        "hex")
+            openssl rand -hex "$secret_bytes"
+            ;;
+        "base64")
+            openssl rand "$secret_bytes" | openssl base64 -A
+            ;;
+        "base64url")
+            openssl rand "$secret_bytes" | openssl base64 -A | tr '+/' '-_' | tr -d '='
+            ;;
+        *)
+            echo "Unsupported secret encoding: $secret_encoding" >&2
+            return 1
+            ;;
+    esac
 }
 
+# Write the JWT secret to the [jwt] section. An existing secret is replaced
+# whatever quotes it was written with, keys of other sections are left alone
 update_config_file() {
     local jwt_secret="$1"
 
-    if ! grep -q "\[jwt\]" "$PROJECT_CONFIG_FILE"; then
+    if ! grep -q "^\[jwt\]" "$PROJECT_CONFIG_FILE"; then
         echo "[jwt]" >> "$PROJECT_CONFIG_FILE"
     fi
 
-    if grep -q "secret" "$PROJECT_CONFIG_FILE"; then
-        sed -i -E "s|secret\s*=\s*\"[^\"]*\"|secret=\"$jwt_secret\"|" "$PROJECT_CONFIG_FILE"
+    if sed -n "/^\[jwt\]/,/^\[/p" "$PROJECT_CONFIG_FILE" | grep -qE "^[[:space:]]*secret[[:space:]]*="; then
+        sed -i -E "/^\[jwt\]/,/^\[/s|^([[:space:]]*)secret[[:space:]]*=.*|\1secret = '$jwt_secret'|" "$PROJECT_CONFIG_FILE"
     else
-        sed -i -E "/\[jwt\]/a\secret=\"$jwt_secret\"" "$PROJECT_CONFIG_FILE"
+        sed -i -E "/^\[jwt\]/a\secret = '$jwt_secret'" "$PROJECT_CONFIG_FILE"
+    fi
+
+    if [[ "$(get_config_value jwt secret)" != "$jwt_secret" ]]; then
+        stop_script "Failed to write JWT secret to $PROJECT_CONFIG_FILE"
     fi
 }
 
+# Secret size and encoding come from secret_bytes/secret_encoding in [jwt]
 create_jwt_secret() {
     log $CYAN "Starting jwt secret creation"
+    local secret_bytes
+    local secret_encoding
     local secret
-    secret=$(generate_random_secret)
+    secret_bytes=$(get_config_value jwt secret_bytes)
+    secret_encoding=$(get_config_value jwt secret_encoding)
+    secret=$(generate_random_secret "${secret_bytes:-32}" "${secret_encoding:-hex}") \
+        || stop_script "Failed to generate JWT secret of ${secret_bytes:-32} bytes with ${secret_encoding:-hex} encoding"
     update_config_file "$secret"
     log $GREEN "JWT secret created successfully"
 }
 
+# Replace the JWT secret of an existing installation. Tokens issued with the
+# previous secret stop being valid once the web app is restarted
+rotate_jwt_secret() {
+    local restart_flag="$1"
+
+    create_jwt_secret
+    if [[ "$restart_flag" == "--restart" ]]; then
+        restart_service 'web-app.service'
+    else
+        log $CYAN "Restart web-app.service to apply the new JWT secret"
+    fi
+}
+
 install_tmux_and_start_session() {
     log $CYAN "Checking for tmux installation"
     if ! command -v tmux &> /dev/null; then
@@ -122,6 +248,22 @@ verify_user_data() {
     LOGIN=$(sed -n "${LOGIN_LINE}p" "$PROJECT_CONFIG_FILE" | awk -F "'" '{print $2}')
     PASSWORD=$(sed -n "${PASSWORD_LINE}p" "$PROJECT_CONFIG_FILE" | awk -F "'" '{print $2}')
 
+    # Validate user login characters. The length is checked below, so an
+    # empty login is reported as too short
+    local LOGIN_PATTERN='^[a-zA-Z0-9_.-]*$'
+    local RESERVED_LOGINS="admin root postgres"
+    if ! matches_ascii "$LOGIN" "$LOGIN_PATTERN"; then
+        stop_script "User login may contain only latin letters, digits, '_', '.' and '-'"
+    fi
+    if [[ $LOGIN =~ ^[0-9] ]]; then
+        stop_script "User login must not start with a digit"
+    fi
+    for reserved_login in $RESERVED_LOGINS; do
+        if [[ "$LOGIN" == "$reserved_login" ]]; then
+            stop_script "User login '$LOGIN' is reserved. Reserved logins: $RESERVED_LOGINS"
+        fi
+    done
+
     # Validate user login
     if [[ ${#LOGIN} -ge 5 && ${#LOGIN} -le 30 ]]; then
         log $GREEN "User login is valid"
@@ -134,6 +276,9 @@ verify_user_data() {
     fi
 
     # Validate user password
+    if [[ "$PASSWORD" == "$LOGIN" ]]; then
+        stop_script "User password must not be equal to the user login"
+    fi
     if [[ ${#PASSWORD} -ge 5 ]]; then
         log $GREEN "User password is valid"
     else
@@ -145,6 +290,73 @@ verify_user_data() {
     fi
 }
 
+# Check the config values the services rely on, so a mistake stops the
+# installation instead of surfacing in a service later. All problems are
+# reported at once
+verify_config() {
+    log $CYAN "Config verification"
+
+    local PORT_PATTERN='^[1-9][0-9]{0,4}$'
+    local HOST_PATTERN='^([A-Za-z0-9]([A-Za-z0-9.-]*[A-Za-z0-9])?|[0-9A-Fa-f:]*:[0-9A-Fa-f:]*)$'
+    local NUMBER_PATTERN='^[1-9][0-9]*$'
+    local DSN_PATTERN='^https?://[^@/]+@[^/]+/[0-9]+$'
+    local JWT_ALGORITHMS="HS256 HS384 HS512"
+    local BACKUPERS="restic"
+    local -A port_keys=()
+    local errors=()
+    local key value
+
+    for key in database.port rabbitmq.port web_app.port prometheus.port notifications.email.smtp_port; do
+        value=$(get_config_value "${key%.*}" "${key##*.}")
+        if ! matches_ascii "$value" "$PORT_PATTERN" || [ "$value" -gt 65535 ]; then
+            errors+=("$key must be a port number between 1 and 65535, got '$value'")
+        elif [[ $key != notifications.* ]]; then
+            # The SMTP port belongs to a remote server and may match a local one
+            if [ -n "${port_keys[$value]}" ]; then
+                errors+=("$key has the same value $value as ${port_keys[$value]}")
+            fi
+            port_keys[$value]=$key
+        fi
+    done
+
+    for key in database.host rabbitmq.host web_app.host prometheus.host; do
+        value=$(get_config_value "${key%.*}" "${key##*.}")
+        if ! matches_ascii "$value" "$HOST_PATTERN"; then
+            errors+=("$key must be an IP address or a hostname, got '$value'")
+        fi
+    done
+
+    for key in jwt.access_token_expiration_minutes jwt.refresh_token_expiration_days; do
+        value=$(get_config_value "${key%.*}" "${key##*.}")
+        if ! matches_ascii "$value" "$NUMBER_PATTERN"; then
+            errors+=("$key must be a positive number, got '$value'")
+        fi
+    done
+
+    value=$(get_config_value jwt algorithm)
+    if [[ " $JWT_ALGORITHMS " != *" $value "* ]]; then
+        errors+=("jwt.algorithm must be one of: $JWT_ALGORITHMS, got '$value'")
+    fi
+
+    value=$(get_config_value sentry dsn)
+    if [ -n "$value" ] && ! matches_ascii "$value" "$DSN_PATTERN"; then
+        errors+=("sentry.dsn must be empty or a DSN like https://<key>@<host>/<project>, got '$value'")
+    fi
+
+    value=$(get_config_value backup backuper)
+    if [[ " $BACKUPERS " != *" $value "* ]]; then
+        errors+=("backup.backuper must be one of: $BACKUPERS, got '$value'")
+    fi
+
+    if [ ${#errors[@]} -gt 0 ]; then
+        for error in "${errors[@]}"; do
+            log $RED "$PROJECT_CONFIG_FILE: $error"
+        done
+        stop_script "Config verification failed with ${#errors[@]} error(s)"
+    fi
+    log $GREEN "Config is valid"
+}
+
 # Generate SSL self-signed certificate
 generate_certificate() {
     # Параметры
@@ -297,14 +509,16 @@ install_docker() {
         return
     fi
 
+    wait_for_apt_lock
     # Устанавливаем необходимые зависимости
-    execute "sudo apt-get install apt-transport-https ca-certificates curl gnupg-agent software-properties-common -y" "Installing necessary dependencies for Docker"
+    execute "sudo apt-get $APT_LOCK_OPTION install apt-transport-https ca-certificates curl gnupg-agent software-properties-common -y" "Installing necessary dependencies for Docker"
     # Добавляем ключ GPG для репозитория Docker
     execute "curl -fsSL https://download.docker.com/linux/$OS_TYPE/gpg | sudo apt-key add -" "Adding GPG key for Docker repository"
     # Добавляем репозиторий Docker
     execute "echo 'deb [arch=$PROC] https://download.docker.com/linux/$OS_TYPE $(lsb_release -cs) stable' | sudo tee /etc/apt/sources.list.d/docker.list > /dev/null" "Adding Docker repository"
     # Обновляем информацию о пакетах и устанавливаем Docker
-    execute "sudo apt-get update && sudo apt-get install docker-ce docker-ce-cli containerd.io -y" "Installing Docker"
+    wait_for_apt_lock
+    execute "sudo apt-get $APT_LOCK_OPTION update && sudo apt-get $APT_LOCK_OPTION install docker-ce docker-ce-cli containerd.io -y" "Installing Docker"
 }
 
 # PostgreSQL installation
@@ -314,8 +528,17 @@ DATABASE_NAME="openvair"
 # Set Docker container name and port number
 DOCKER_CONTAINER_NAME="postgres"
 DATABASE_PORT=$(awk -F " = " '/\[database\]/{flag=1; next} flag && /port/ {print $2; exit}' "$PROJECT_CONFIG_FILE")
+# Seconds to wait for PostgreSQL to accept connections
+DATABASE_WAIT_TIMEOUT=60
+
+# Check if a Docker container with the given name exists, running or not
+container_exists() {
+    local container_name="$1"
+    sudo docker container inspect "$container_name" &> /dev/null
+}
 
-# Run PostgreSQL container in Docker
+# Run PostgreSQL container in Docker. An existing container is started and
+# reused, so the installation can be run again
 run_postgres_container() {
     local message="Creating PostgreSQL Docker container"
     local command="sudo docker run \
@@ -326,12 +549,66 @@ run_postgres_container() {
         -p $DATABASE_PORT:$DATABASE_PORT \
         -d postgres \
         -c 'listen_addresses=*'"
-    execute "$command" "$message"
-    sleep 5
+
+    if ! command -v docker &> /dev/null; then
+        stop_script "Docker is not installed, it is required for the PostgreSQL container"
+    fi
+
+    if container_exists "$DOCKER_CONTAINER_NAME"; then
+        log $GREEN "PostgreSQL container $DOCKER_CONTAINER_NAME already exists, reusing it"
+        execute "sudo docker start $DOCKER_CONTAINER_NAME" "Starting PostgreSQL Docker container"
+        wait_for_postgres
+        verify_postgres_credentials
+    else
+        execute "$command" "$message"
+        wait_for_postgres
+    fi
+}
+
+# Check that the reused container accepts the password of $USER over TCP,
+# where the postgres image requires it (local socket connections are trusted)
+verify_postgres_credentials() {
+    if sudo docker exec -e PGPASSWORD=$USER $DOCKER_CONTAINER_NAME psql -h 127.0.0.1 -U $USER -d postgres -tAc "SELECT 1" &> /dev/null; then
+        log $GREEN "PostgreSQL container $DOCKER_CONTAINER_NAME accepts the credentials of $USER"
+    else
+        stop_script "PostgreSQL container $DOCKER_CONTAINER_NAME does not accept the credentials of $USER, remove the container to recreate it"
+    fi
+}
+
+# Check that a TCP port accepts connections
+check_tcp_port() {
+    local host="$1"
+    local port="$2"
+    timeout 5 bash -c "</dev/tcp/$host/$port" 2> /dev/null
+}
+
+# Wait until PostgreSQL accepts connections inside the container and on the
+# host and port from [database], which the services connect to
+wait_for_postgres() {
+    local host
+    local waited=0
+    host=$(get_config_value database host)
+
+    until sudo docker exec $DOCKER_CONTAINER_NAME pg_isready -q -U $USER && check_tcp_port "$host" "$DATABASE_PORT"; do
+        if [ "$waited" -ge "$DATABASE_WAIT_TIMEOUT" ]; then
+            log $RED "Last lines of the $DOCKER_CONTAINER_NAME container log:"
+            sudo docker logs --tail 20 $DOCKER_CONTAINER_NAME 2>&1 | tee -a "$LOG_FILE"
+            stop_script "Cannot reach PostgreSQL at $host:$DATABASE_PORT after ${DATABASE_WAIT_TIMEOUT}s"
+        fi
+        log $CYAN "Waiting for PostgreSQL at $host:$DATABASE_PORT..."
+        sleep 2
+        waited=$((waited + 2))
+    done
+    log $GREEN "PostgreSQL is accepting connections at $host:$DATABASE_PORT"
 }
 
 # Create database function
 create_database() {
+    if sudo docker exec $DOCKER_CONTAINER_NAME psql -U $USER -tAc "SELECT 1 FROM pg_database WHERE datname = '$DATABASE_NAME'" | grep -q 1; then
+        log $GREEN "Database $DATABASE_NAME already exists"
+        return
+    fi
+
     local command="sudo docker exec -it $DOCKER_CONTAINER_NAME psql -U $USER -c 'CREATE DATABASE $DATABASE_NAME;'"
     local message="Creating database $DATABASE_NAME"
     execute "$command" "$message"
@@ -344,15 +621,63 @@ grant_privileges() {
     execute "$command" "$message"
 }
 
-# Function to create RabbitMQ Docker container
+# Seconds to wait for RabbitMQ to start
+RABBITMQ_WAIT_TIMEOUT=120
+RABBITMQ_CONTAINER_NAME="rabbit"
+
+# Wait until the broker in the RabbitMQ container is running and accepts
+# connections on the given host and port
+wait_for_rabbitmq() {
+    local host="$1"
+    local port="$2"
+    local waited=0
+
+    until sudo docker exec $RABBITMQ_CONTAINER_NAME rabbitmqctl status &> /dev/null && check_tcp_port "$host" "$port"; do
+        if [ "$waited" -ge "$RABBITMQ_WAIT_TIMEOUT" ]; then
+            log $RED "Last lines of the $RABBITMQ_CONTAINER_NAME container log:"
+            sudo docker logs --tail 20 $RABBITMQ_CONTAINER_NAME 2>&1 | tee -a "$LOG_FILE"
+            stop_script "Cannot reach RabbitMQ in container $RABBITMQ_CONTAINER_NAME at $host:$port after ${RABBITMQ_WAIT_TIMEOUT}s"
+        fi
+        log $CYAN "Waiting for RabbitMQ at $host:$port..."
+        sleep 5
+        waited=$((waited + 5))
+    done
+    log $GREEN "RabbitMQ is accepting connections at $host:$port"
+}
+
+# Make the user of a reused RabbitMQ container match [rabbitmq]. The default
+# user of the image is only created with the container, so a changed user or
+# password is applied here. The password is kept out of the log
+reconcile_rabbitmq_user() {
+    local user="$1"
+    local password="$2"
+
+    if sudo docker exec $RABBITMQ_CONTAINER_NAME rabbitmqctl list_users --silent | awk '{print $1}' | grep -qx "$user"; then
+        sudo docker exec $RABBITMQ_CONTAINER_NAME rabbitmqctl change_password "$user" "$password" > /dev/null \
+            || stop_script "Failed to update the password of RabbitMQ user $user"
+    else
+        sudo docker exec $RABBITMQ_CONTAINER_NAME rabbitmqctl add_user "$user" "$password" > /dev/null \
+            || stop_script "Failed to add RabbitMQ user $user"
+    fi
+    sudo docker exec $RABBITMQ_CONTAINER_NAME rabbitmqctl set_permissions -p / "$user" ".*" ".*" ".*" > /dev/null \
+        || stop_script "Failed to set permissions of RabbitMQ user $user"
+    log $GREEN "RabbitMQ user $user is configured"
+}
+
+# Function to create RabbitMQ Docker container. An existing container is
+# started and reused, so the installation can be run again
 create_rabbitmq_container() {
+    if [ "$(get_config_value messaging transport)" != "rabbitmq" ]; then
+        log $GREEN "Messaging transport is not rabbitmq, skipping RabbitMQ container"
+        return
+    fi
+
     log $CYAN "Start to create RabbitMQ Docker container..."
     log $CYAN "Fetch RabbitMQ configuration from project_config.toml"
-    local section_line=$(awk '/\[rabbitmq\]/{print NR}' "$PROJECT_CONFIG_FILE")
-    local rabbitmq_user=$(awk -F "'" -v section_line=$((section_line + 1)) 'NR==section_line {print $2}' "$PROJECT_CONFIG_FILE")
-    local rabbitmq_password=$(awk -F "'" -v section_line=$((section_line + 2)) 'NR==section_line {print $2}' "$PROJECT_CONFIG_FILE")
-    local rabbitmq_host=$(awk -F "'" -v section_line=$((section_line + 3)) 'NR==section_line {print $2}' "$PROJECT_CONFIG_FILE")
-    local rabbitmq_port=$(awk -F " = " -v section_line=$((section_line + 4)) 'NR==section_line {print $2}' "$PROJECT_CONFIG_FILE")
+    local rabbitmq_user=$(get_config_value rabbitmq user)
+    local rabbitmq_password=$(get_config_value rabbitmq password)
+    local rabbitmq_host=$(get_config_value rabbitmq host)
+    local rabbitmq_port=$(get_config_value rabbitmq port)
     log $GREEN "RabbitMQ configuration data fetched successfully"
 
     # Check and adjust RabbitMQ_HOST if it's set to "localhost"
@@ -363,14 +688,22 @@ create_rabbitmq_container() {
     # Prepare the docker run command
     local command="sudo docker run -d \
         --hostname $(hostname) \
-        --name rabbit \
+        --name $RABBITMQ_CONTAINER_NAME \
         -e RABBITMQ_DEFAULT_USER=$rabbitmq_user \
         -e RABBITMQ_DEFAULT_PASS=$rabbitmq_password \
         -p $rabbitmq_host:$rabbitmq_port:$rabbitmq_port \
         --restart unless-stopped rabbitmq:3.11"
 
     # Execute the command
-    execute "$command" "Creating RabbitMQ Docker container"
+    if container_exists "$RABBITMQ_CONTAINER_NAME"; then
+        log $GREEN "RabbitMQ container $RABBITMQ_CONTAINER_NAME already exists, reusing it"
+        execute "sudo docker start $RABBITMQ_CONTAINER_NAME" "Starting RabbitMQ Docker container"
+        wait_for_rabbitmq "$rabbitmq_host" "$rabbitmq_port"
+        reconcile_rabbitmq_user "$rabbitmq_user" "$rabbitmq_password"
+    else
+        execute "$command" "Creating RabbitMQ Docker container"
+        wait_for_rabbitmq "$rabbitmq_host" "$rabbitmq_port"
+    fi
 }
 
 # Install SNMP packages
@@ -398,6 +731,7 @@ configure_snmp_conf() {
 # Function to make migrations
 make_migrations() {
     log $CYAN "Starting alembic migrations..."
+    wait_for_postgres
 
     # Navigate to the project path
     if cd "$PROJECT_PATH"; then
@@ -420,6 +754,26 @@ download_package(){
   execute "curl -LO \"$url\"" "$message"
 }
 
+# Function to verify a downloaded release archive against sha256sums.txt
+# published in the same GitHub release
+verify_release_checksum(){
+  local release_url="$1"
+  local file="$2"
+  local checksums_file="${file}.sha256sums"
+  local checksum_line
+
+  execute "curl -fsSL -o \"$checksums_file\" \"$release_url/sha256sums.txt\"" "Downloading checksums for $file"
+  checksum_line=$(awk -v file="$file" '$2 == file' "$checksums_file")
+  rm -f "$checksums_file"
+  if [ -z "$checksum_line" ]; then
+    stop_script "No checksum for $file in $release_url/sha256sums.txt"
+  fi
+  if ! echo "$checksum_line" | sha256sum -c --status; then
+    stop_script "Checksum mismatch for $file, the download is corrupted or was tampered with"
+  fi
+  log $GREEN "Checksum of $file verified"
+}
+
 # Function to unzip an archive
 unzip_arch(){
   local archive="$1"
@@ -561,11 +915,13 @@ install_prometheus() {
   local DEPENDENCY="prometheus"
   local VERSION=$(grep "^${DEPENDENCY}==" "${DEPENDENCIES_FILE}" | sed "s/^${DEPENDENCY}==//")
   local PRODUCT="${DEPENDENCY}-${VERSION}.linux-${PROC}"
-  local URL="https://github.com/prometheus/prometheus/releases/download/v${VERSION}/${PRODUCT}.tar.gz"
+  local RELEASE_URL="https://github.com/prometheus/prometheus/releases/download/v${VERSION}"
+  local URL="${RELEASE_URL}/${PRODUCT}.tar.gz"
   local downloaded_file="${PRODUCT}.tar.gz"
 
   go_to_home_dir
   download_package "$URL"
+  verify_release_checksum "$RELEASE_URL" "$downloaded_file"
   unzip_arch "$downloaded_file" 
   go_to_dir "$PRODUCT"
   replace_binary_files_to_local_bin "prometheus" "promtool"
@@ -642,10 +998,12 @@ install_node_exporter() {
   fi
 
   local PRODUCT="${DEPENDENCY}-${VERSION}.linux-${PROC}"
-  local URL="https://github.com/prometheus/node_exporter/releases/download/v${VERSION}/${PRODUCT}.tar.gz"
+  local RELEASE_URL="https://github.com/prometheus/node_exporter/releases/download/v${VERSION}"
+  local URL="${RELEASE_URL}/${PRODUCT}.tar.gz"
   local downloaded_file="${PRODUCT}.tar.gz"
 
   download_package "$URL"
+  verify_release_checksum "$RELEASE_URL" "$downloaded_file"
   unzip_arch "$downloaded_file"
   go_to_dir "$PRODUCT"
   replace_binary_files_to_local_bin "node_exporter"
@@ -666,8 +1024,9 @@ install_node_exporter() {
 
 # Install open-iscsi
 install_open_iscsi() {
-    local command="yes | sudo apt install open-iscsi"
+    local command="yes | sudo apt $APT_LOCK_OPTION install open-iscsi"
     local message="Installing open-iscsi"
+    wait_for_apt_lock
     execute "$command" "$message" 
 }
 
@@ -681,14 +1040,16 @@ clone_novnc() {
 # Install jq
 install_jq(){
     local message="Installing jq"
-    execute "sudo apt-get install jq -y" "$message"
+    wait_for_apt_lock
+    execute "sudo apt-get $APT_LOCK_OPTION install jq -y" "$message"
 }
 
 install_restic(){
-  local apt_get_command="sudo apt-get install restic"
+  local apt_get_command="sudo apt-get $APT_LOCK_OPTION install restic"
   local self_update_command="sudo restic self-update" 
   local install_message="Installing restic"
   local update_message="Updating restic"
+  wait_for_apt_lock
   execute "$apt_get_command" "$install_message"
   execute "$self_update_command" "$update_message"
 }
@@ -749,16 +1110,30 @@ print(hash)
 """)
 }
 
-# Create default user
-create_default_user() {
-    local message="Createing default user"
-    local command="sudo docker exec -it $DOCKER_CONTAINER_NAME \
-        psql -U $USER -d $DATABASE_NAME -c \"INSERT INTO users \
-        (id, username, password) \
-        VALUES ('6777383c-56c3-44b3-8243-2fd5b819d3c9', '$LOGIN', \
-        NULL, 't', '$HASHED_PASSWORD')\""
-    execute "$command" "$message"
-    sleep 5
+# Seconds to wait for the web app to accept connections after a restart
+WEB_APP_WAIT_TIMEOUT=60
+
+# Restart the web app and wait until it accepts connections on the host and
+# port from [web_app]. The end of its journal is shown if it does not come up
+restart_web_app() {
+    local host
+    local port
+    local waited=0
+    host=$(get_config_value web_app host)
+    port=$(get_config_value web_app port)
+
+    restart_service 'web-app.service'
+    until check_tcp_port "$host" "$port"; do
+        if [ "$waited" -ge "$WEB_APP_WAIT_TIMEOUT" ]; then
+            log $RED "Last lines of the web-app.service journal:"
+            sudo journalctl -u web-app.service -n 20 --no-pager | tee -a "$LOG_FILE"
+            stop_script "web-app.service is not accepting connections at $host:$port after ${WEB_APP_WAIT_TIMEOUT}s"
+        fi
+        log $CYAN "Waiting for web-app.service at $host:$port..."
+        sleep 2
+        waited=$((waited + 2))
+    done
+    log $GREEN "web-app.service is accepting connections at $host:$port"
 }
 
 # ============ Printing the final message =================
@@ -796,6 +1171,8 @@ extract_value_from_config() {
 print_final_message() {
     local IP=$(extract_value_from_config "host")
     local PORT=$(extract_value_from_config "port")
+    local PROMETHEUS_IP=$(get_config_value prometheus host)
+    local PROMETHEUS_PORT=$(get_config_value prometheus port)
     terminal_width=$(tput cols)
     line=$(printf "%*s" "$terminal_width" | tr ' ' '-')
 
@@ -816,6 +1193,8 @@ print_final_message() {
 
     print_with_padding "ADDRESS  https://${IP}:${PORT}" $padding_for_main_msg
     print_with_padding "API-DOCS $api_docs_url" $padding_for_main_msg
+    print_with_padding "DOCS     https://${IP}:${PORT}/docs/" $padding_for_main_msg
+    print_with_padding "METRICS  https://${PROMETHEUS_IP}:${PROMETHEUS_PORT}" $padding_for_main_msg
     print_with_padding "LOGIN    $LOGIN" $padding_for_main_msg
     print_with_padding "PASSWORD $PASSWORD" $padding_for_main_msg
 
@@ -825,16 +1204,33 @@ print_final_message() {
     printf "%s\n" "$line"
 }
 
+# Check if a user with the given login exists in the users table
+user_exists() {
+    local login="$1"
+    sudo docker exec $DOCKER_CONTAINER_NAME psql -U $USER -d $DATABASE_NAME -tAc "SELECT 1 FROM users WHERE username = '$login'" | grep -q 1
+}
+
+# Create the default user from [default_user]. An existing user is kept as
+# is, so the installation can be run again
 create_default_user() {
+    if user_exists "$LOGIN"; then
+        log $GREEN "Default user $LOGIN already exists"
+        return
+    fi
+
     log $CYAN "Creating default user"
-    sudo docker exec -it $DOCKER_CONTAINER_NAME psql -U $USER -d $DATABASE_NAME -c "INSERT INTO users VALUES ('0b677738-34ff-4f9e-b1f6-5962065c0207', '$LOGIN', NULL, 't', '$HASHED_PASSWORD')" || stop_script "Failure while adding deafault user"
+    sudo docker exec $DOCKER_CONTAINER_NAME psql -U $USER -d $DATABASE_NAME -c "INSERT INTO users (id, username, email, is_superuser, hashed_password) VALUES ('0b677738-34ff-4f9e-b1f6-5962065c0207', '$LOGIN', NULL, 't', '$HASHED_PASSWORD')" || stop_script "Failure while adding default user"
+    user_exists "$LOGIN" || stop_script "Default user $LOGIN is missing from the users table after adding it"
     log $GREEN "Default user was added successfully"
 }
 
 # Run the main installation steps
 main() {
     install_tmux_and_start_session
+    restrict_file_permissions "$PROJECT_CONFIG_FILE"
+    restrict_file_permissions "$LOG_FILE"
     verify_user_data
+    verify_config
     create_jwt_secret
     get_os_type
     go_to_home_dir
@@ -873,15 +1269,26 @@ main() {
     create_default_user
     install_uv
     install_documentation
-    restart_service 'web-app.service'
+    restart_web_app
     print_final_message
 }
 
-main
+# Run the installation only when the script is executed, sourcing it just
+# loads the functions
+if [[ "${BASH_SOURCE[0]}" == "$0" ]]; then
+    # Rotate the JWT secret without reinstalling:
+    #   bash install.sh rotate-jwt-secret [--restart]
+    if [[ "$1" == "rotate-jwt-secret" ]]; then
+        rotate_jwt_secret "$2"
+        exit 0
+    fi
 
-# Check if script is running inside the tmux session
-if [[ "$1" == "tmux" ]]; then
-    # Press any key to exit tmux session
-    read -n 1 -s -r -p "Press any key to exit tmux session"
-    exit 0
+    main
+
+    # Check if script is running inside the tmux session
+    if [[ "$1" == "tmux" ]]; then
+        # Press any key to exit tmux session
+        read -n 1 -s -r -p "Press any key to exit tmux session"
+        exit 0
+    fi
 fi
@@ -0,0 +1,12 @@
+# noqa: D100
+from pathlib import Path
+
+import pytest
+
+from openvair.tests.install.utils import InstallScript
+
+
+@pytest.fixture(scope='function')
+def install_script(tmp_path: Path) -> InstallScript:
+    """Provides install.sh functions with stubbed system commands."""
+    return InstallScript(tmp_path)
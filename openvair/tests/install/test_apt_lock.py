@@ -0,0 +1,69 @@
+"""Tests for wait_for_apt_lock from install.sh.
+
+This test suite covers:
+- Waiting while another process holds the apt/dpkg locks.
+- Stopping when the lock is held longer than APT_LOCK_TIMEOUT.
+- Warning once when fuser is not available.
+"""
+
+from openvair.tests.install.utils import InstallScript
+
+BUSY_POLLS = 2
+
+
+def test_wait_for_apt_lock_free(install_script: InstallScript) -> None:
+    """Test that a free lock is not waited for."""
+    install_script.stub('fuser')
+
+    result = install_script.run('wait_for_apt_lock')
+
+    assert result.returncode == 0
+    assert 'Waiting for apt lock' not in result.stdout
+    assert install_script.calls('fuser') == [
+        '/var/lib/dpkg/lock-frontend /var/lib/dpkg/lock '
+        '/var/lib/apt/lists/lock'
+    ]
+
+
+def test_wait_for_apt_lock_waits_for_holder(
+    install_script: InstallScript,
+) -> None:
+    """Test that the holder PID is reported until the lock is released."""
+    install_script.stub(
+        'fuser',
+        f'if [ $(grep -c "^fuser" "{install_script.calls_file}") '
+        f'-le {BUSY_POLLS} ]; then echo " 1234"; fi',
+    )
+
+    result = install_script.run('wait_for_apt_lock')
+
+    assert result.returncode == 0
+    assert (
+        result.stdout.count('Waiting for apt lock held by PID 1234')
+        == BUSY_POLLS
+    )
+
+
+def test_wait_for_apt_lock_timeout(install_script: InstallScript) -> None:
+    """Test that a lock held for too long stops the script."""
+    install_script.stub('fuser', 'echo " 1234"')
+
+    result = install_script.run('APT_LOCK_TIMEOUT=10; wait_for_apt_lock')
+
+    assert result.returncode == 1
+    assert (
+        'STOP: Apt lock is still held by PID 1234 after 10s' in result.stdout
+    )
+
+
+def test_wait_for_apt_lock_without_fuser_warns_once(
+    install_script: InstallScript,
+) -> None:
+    """Test that a missing fuser is reported once, not on every apt call."""
+    result = install_script.run(
+        'wait_for_apt_lock; wait_for_apt_lock',
+        env={'PATH': str(install_script.bin_dir)},
+    )
+
+    assert result.returncode == 0
+    assert result.stdout.count('fuser is not installed') == 1
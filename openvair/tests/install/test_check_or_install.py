@@ -0,0 +1,130 @@
+"""Tests for is_package_installed and check_or_install from install.sh.
+
+This test suite covers:
+- Detection of installed, missing and arch-qualified packages by the real
+  dpkg-query.
+- Installing only the missing packages of a list, with dpkg-query and
+  apt-get stubbed.
+"""
+
+import shutil
+import subprocess
+from typing import List
+
+import pytest
+
+from openvair.tests.install.utils import InstallScript
+
+APT_LOCK_OPTION = '-o DPkg::Lock::Timeout=600'
+HAS_DPKG = shutil.which('dpkg-query') is not None
+requires_dpkg = pytest.mark.skipif(not HAS_DPKG, reason='dpkg is required')
+
+
+def native_architecture() -> str:
+    """Returns the dpkg architecture of the system, e.g. amd64."""
+    return subprocess.run(  # noqa: S603
+        [str(shutil.which('dpkg')), '--print-architecture'],
+        capture_output=True,
+        text=True,
+        check=True,
+    ).stdout.strip()
+
+
+def stub_installed_packages(
+    install_script: InstallScript,
+    packages: str,
+) -> None:
+    """Stubs dpkg-query so that only the given packages are installed."""
+    install_script.stub(
+        'dpkg-query',
+        f'for package in {packages}; do\n'
+        '    if [ "${!#}" == "$package" ]; then\n'
+        "        printf 'install ok installed'\n"
+        '        exit 0\n'
+        '    fi\n'
+        'done\n'
+        'exit 1',
+    )
+    install_script.stub('apt-get')
+    install_script.stub('fuser')
+
+
+@requires_dpkg
+def test_is_package_installed_installed(
+    install_script: InstallScript,
+) -> None:
+    """Test that dpkg itself is detected as installed."""
+    result = install_script.run('is_package_installed dpkg')
+
+    assert result.returncode == 0
+
+
+@requires_dpkg
+def test_is_package_installed_missing(install_script: InstallScript) -> None:
+    """Test that an unknown package is not detected as installed."""
+    result = install_script.run('is_package_installed openvair-missing-pkg')
+
+    assert result.returncode == 1
+
+
+@requires_dpkg
+def test_is_package_installed_arch_qualified(
+    install_script: InstallScript,
+) -> None:
+    """Test that a package with an architecture suffix is detected."""
+    result = install_script.run(
+        f'is_package_installed dpkg:{native_architecture()}'
+    )
+
+    assert result.returncode == 0
+
+
+@requires_dpkg
+def test_is_package_installed_substring(
+    install_script: InstallScript,
+) -> None:
+    """Test that a prefix of an installed package name does not match."""
+    result = install_script.run('is_package_installed dpk')
+
+    assert result.returncode == 1
+
+
+@pytest.mark.parametrize(
+    ('installed', 'packages', 'expected'),
+    [
+        ('tmux', 'tmux', []),
+        ('tmux', 'jq', [f'{APT_LOCK_OPTION} install -y jq']),
+        ('libjq1', 'jq', [f'{APT_LOCK_OPTION} install -y jq']),
+        (
+            'nfs-common',
+            'nfs-common xfsprogs',
+            [f'{APT_LOCK_OPTION} install -y xfsprogs'],
+        ),
+        ('nfs-common xfsprogs', 'nfs-common xfsprogs', []),
+        ('', 'snmp snmpd', [f'{APT_LOCK_OPTION} install -y snmp snmpd']),
+    ],
+)
+def test_check_or_install_installs_missing_packages(
+    install_script: InstallScript,
+    installed: str,
+    packages: str,
+    expected: List[str],
+) -> None:
+    """Test that apt-get is called only for packages that are missing."""
+    stub_installed_packages(install_script, installed)
+
+    result = install_script.run(f'check_or_install "{packages}"')
+
+    assert result.returncode == 0
+    assert install_script.calls('apt-get') == expected
+
+
+def test_check_or_install_apt_failure(install_script: InstallScript) -> None:
+    """Test that a failed apt-get install stops the script."""
+    stub_installed_packages(install_script, '')
+    install_script.stub('apt-get', 'exit 100')
+
+    result = install_script.run('check_or_install jq')
+
+    assert result.returncode == 1
+    assert 'STOP: Failed to install jq' in result.stdout
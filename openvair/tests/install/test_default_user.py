@@ -0,0 +1,71 @@
+"""Tests for create_default_user from install.sh.
+
+This test suite covers:
+- Adding the default user when it does not exist.
+- Keeping an existing default user.
+- Stopping when the user is missing after adding it.
+"""
+
+from openvair.tests.install.utils import InstallScript
+
+DEFAULT_USER = "LOGIN=admin1; HASHED_PASSWORD='$2b$12$hash'"
+
+
+def inserted_users(install_script: InstallScript) -> int:
+    """Returns how many times the default user was inserted."""
+    return sum(
+        'INSERT INTO users' in call for call in install_script.calls('docker')
+    )
+
+
+def test_create_default_user_adds_missing_user(
+    install_script: InstallScript,
+) -> None:
+    """Test that a missing user is inserted and then found."""
+    install_script.stub(
+        'docker',
+        f'if [[ "$*" == *"SELECT 1 FROM users"* ]] && '
+        f'grep -q "INSERT INTO users" "{install_script.calls_file}"; then\n'
+        '    echo 1\n'
+        'fi',
+    )
+
+    result = install_script.run(f'{DEFAULT_USER}; create_default_user')
+
+    assert result.returncode == 0
+    assert 'Default user was added successfully' in result.stdout
+    assert inserted_users(install_script) == 1
+    insert = next(
+        call
+        for call in install_script.calls('docker')
+        if 'INSERT INTO users' in call
+    )
+    assert "'admin1', NULL, 't', '$2b$12$hash'" in insert
+
+
+def test_create_default_user_skips_existing_user(
+    install_script: InstallScript,
+) -> None:
+    """Test that an existing user is not inserted again."""
+    install_script.stub('docker', 'echo 1')
+
+    result = install_script.run(f'{DEFAULT_USER}; create_default_user')
+
+    assert result.returncode == 0
+    assert 'Default user admin1 already exists' in result.stdout
+    assert inserted_users(install_script) == 0
+
+
+def test_create_default_user_missing_after_insert(
+    install_script: InstallScript,
+) -> None:
+    """Test that the script stops when the inserted user is not found."""
+    install_script.stub('docker')
+
+    result = install_script.run(f'{DEFAULT_USER}; create_default_user')
+
+    assert result.returncode == 1
+    assert (
+        'STOP: Default user admin1 is missing from the users table'
+        in result.stdout
+    )
@@ -0,0 +1,44 @@
+"""Tests for restrict_file_permissions from install.sh.
+
+This test suite covers:
+- Restricting a file readable by others to the owner.
+- Leaving a file that is already private untouched.
+"""
+
+import stat
+
+from openvair.tests.install.utils import InstallScript
+
+PRIVATE_FILE_MODE = 0o600
+
+
+def test_restrict_file_permissions_tightens_mode(
+    install_script: InstallScript,
+) -> None:
+    """Test that a world readable file is restricted with a warning."""
+    install_script.config_file.chmod(0o644)
+
+    result = install_script.run(
+        f'restrict_file_permissions "{install_script.config_file}"'
+    )
+
+    assert result.returncode == 0
+    mode = stat.S_IMODE(install_script.config_file.stat().st_mode)
+    assert mode == PRIVATE_FILE_MODE
+    assert 'has permissions 644, restricting them to 600' in result.stdout
+
+
+def test_restrict_file_permissions_keeps_private_file(
+    install_script: InstallScript,
+) -> None:
+    """Test that a file with mode 0600 is left as is without a warning."""
+    install_script.config_file.chmod(PRIVATE_FILE_MODE)
+
+    result = install_script.run(
+        f'restrict_file_permissions "{install_script.config_file}"'
+    )
+
+    assert result.returncode == 0
+    mode = stat.S_IMODE(install_script.config_file.stat().st_mode)
+    assert mode == PRIVATE_FILE_MODE
+    assert 'restricting' not in result.stdout
@@ -0,0 +1,188 @@
+"""Tests for the JWT secret functions from install.sh.
+
+This test suite covers:
+- Secret size and encoding of generate_random_secret, and invalid input.
+- Writing the secret according to secret_bytes/secret_encoding in [jwt].
+- Replacing an existing secret of [jwt] only, whatever its quotes.
+- Rotating the secret with and without restarting the web app.
+"""
+
+import re
+import shutil
+
+import pytest
+
+from openvair.tests.install.utils import InstallScript
+
+requires_openssl = pytest.mark.skipif(
+    shutil.which('openssl') is None, reason='openssl is required'
+)
+
+
+def jwt_secret(install_script: InstallScript) -> str:
+    """Returns the secret of [jwt] from the test config."""
+    return install_script.run('get_config_value jwt secret').stdout.strip()
+
+
+@requires_openssl
+@pytest.mark.parametrize(
+    ('secret_bytes', 'encoding', 'pattern'),
+    [
+        ('32', 'hex', r'[0-9a-f]{64}'),
+        ('64', 'hex', r'[0-9a-f]{128}'),
+        ('32', 'base64', r'[A-Za-z0-9+/]{43}='),
+        ('64', 'base64url', r'[A-Za-z0-9_-]{86}'),
+    ],
+)
+def test_generate_random_secret_encodings(
+    install_script: InstallScript,
+    secret_bytes: str,
+    encoding: str,
+    pattern: str,
+) -> None:
+    """Test the length and alphabet of each encoding."""
+    result = install_script.run(
+        f'generate_random_secret {secret_bytes} {encoding}'
+    )
+
+    assert result.returncode == 0
+    assert re.fullmatch(pattern, result.stdout.strip())
+
+
+@requires_openssl
+def test_generate_random_secret_defaults(
+    install_script: InstallScript,
+) -> None:
+    """Test that the default secret is 32 bytes in hex."""
+    result = install_script.run('generate_random_secret')
+
+    assert re.fullmatch(r'[0-9a-f]{64}', result.stdout.strip())
+
+
+@pytest.mark.parametrize(
+    ('arguments', 'message'),
+    [
+        ('0 hex', 'Secret size must be a positive number of bytes: 0'),
+        ('32x hex', 'Secret size must be a positive number of bytes: 32x'),
+        ('32 base32', 'Unsupported secret encoding: base32'),
+    ],
+)
+def test_generate_random_secret_invalid(
+    install_script: InstallScript,
+    arguments: str,
+    message: str,
+) -> None:
+    """Test that an invalid size or encoding fails with a message."""
+    result = install_script.run(f'generate_random_secret {arguments} 2>&1')
+
+    assert result.returncode == 1
+    assert message in result.stdout
+
+
+@requires_openssl
+def test_create_jwt_secret_uses_configured_policy(
+    install_script: InstallScript,
+) -> None:
+    """Test that secret_bytes and secret_encoding define the secret."""
+    install_script.set_config_value('jwt', 'secret_bytes', '64  # policy')
+    install_script.set_config_value('jwt', 'secret_encoding', "'base64url'")
+
+    result = install_script.run('create_jwt_secret')
+
+    assert result.returncode == 0
+    assert re.fullmatch(r'[A-Za-z0-9_-]{86}', jwt_secret(install_script))
+
+
+def test_create_jwt_secret_invalid_policy(
+    install_script: InstallScript,
+) -> None:
+    """Test that an invalid policy stops the script."""
+    install_script.set_config_value('jwt', 'secret_bytes', '0')
+
+    result = install_script.run('create_jwt_secret')
+
+    assert result.returncode == 1
+    assert (
+        'STOP: Failed to generate JWT secret of 0 bytes with hex encoding'
+        in result.stdout
+    )
+
+
+@pytest.mark.parametrize('quote', ["'", '"'])
+def test_update_config_file_replaces_existing_secret(
+    install_script: InstallScript,
+    quote: str,
+) -> None:
+    """Test that an existing secret is replaced whatever its quotes."""
+    config = install_script.config_file.read_text()
+    install_script.config_file.write_text(
+        config.replace('[jwt]\n', f'[jwt]\nsecret = {quote}old{quote}\n')
+    )
+
+    result = install_script.run('update_config_file new')
+
+    assert result.returncode == 0
+    assert jwt_secret(install_script) == 'new'
+    config = install_script.config_file.read_text()
+    assert 'old' not in config
+    assert config.count('secret =') == 1
+
+
+def test_update_config_file_keeps_other_sections(
+    install_script: InstallScript,
+) -> None:
+    """Test that a secret key outside of [jwt] is left as is."""
+    config = install_script.config_file.read_text()
+    install_script.config_file.write_text(
+        config.replace('[sentry]\n', "[sentry]\nsecret = 'sentry'\n")
+    )
+
+    result = install_script.run('update_config_file new')
+
+    assert result.returncode == 0
+    assert jwt_secret(install_script) == 'new'
+    assert "[sentry]\nsecret = 'sentry'" in (
+        install_script.config_file.read_text()
+    )
+
+
+def test_update_config_file_adds_jwt_section(
+    install_script: InstallScript,
+) -> None:
+    """Test that the [jwt] section is created when it is missing."""
+    install_script.config_file.write_text('[database]\nport = 5432\n')
+
+    result = install_script.run('update_config_file new')
+
+    assert result.returncode == 0
+    assert jwt_secret(install_script) == 'new'
+
+
+@requires_openssl
+def test_rotate_jwt_secret_changes_secret(
+    install_script: InstallScript,
+) -> None:
+    """Test that rotation replaces the stored secret without a restart."""
+    install_script.run('create_jwt_secret')
+    old_secret = jwt_secret(install_script)
+    install_script.stub('systemctl')
+
+    result = install_script.run('rotate_jwt_secret')
+
+    assert result.returncode == 0
+    assert jwt_secret(install_script) not in ('', old_secret)
+    assert 'Restart web-app.service to apply' in result.stdout
+    assert install_script.calls('systemctl') == []
+
+
+@requires_openssl
+def test_rotate_jwt_secret_restarts_web_app(
+    install_script: InstallScript,
+) -> None:
+    """Test that --restart restarts the web app."""
+    install_script.stub('systemctl')
+
+    result = install_script.run('rotate_jwt_secret --restart')
+
+    assert result.returncode == 0
+    assert install_script.calls('systemctl') == ['restart web-app.service']
@@ -0,0 +1,176 @@
+"""Tests for the PostgreSQL container functions from install.sh.
+
+This test suite covers:
+- Creating the container, or starting an existing one instead.
+- Stopping when Docker is missing or the reused container rejects the
+  credentials.
+- Creating the database only when it does not exist.
+- Waiting until PostgreSQL accepts connections.
+- Stopping with the database address when it never becomes reachable.
+"""
+
+from openvair.tests.install.utils import InstallScript
+
+DATABASE_PORT = 'DATABASE_PORT=5432'
+FAILED_POLLS = 2
+
+
+def test_run_postgres_container_creates_container(
+    install_script: InstallScript,
+) -> None:
+    """Test that a missing container is created."""
+    install_script.stub(
+        'docker', 'if [ "$1" == "container" ]; then exit 1; fi'
+    )
+    install_script.stub('timeout')
+
+    result = install_script.run(f'{DATABASE_PORT}; run_postgres_container')
+
+    assert result.returncode == 0
+    docker_calls = install_script.calls('docker')
+    assert any(call.startswith('run --name postgres') for call in docker_calls)
+    assert 'start postgres' not in docker_calls
+
+
+def test_run_postgres_container_reuses_container(
+    install_script: InstallScript,
+) -> None:
+    """Test that an existing container is started instead of recreated."""
+    install_script.stub('docker')
+    install_script.stub('timeout')
+
+    result = install_script.run(f'{DATABASE_PORT}; run_postgres_container')
+
+    assert result.returncode == 0
+    assert 'already exists, reusing it' in result.stdout
+    docker_calls = install_script.calls('docker')
+    assert 'start postgres' in docker_calls
+    assert not any(call.startswith('run ') for call in docker_calls)
+
+
+def test_run_postgres_container_checks_credentials_of_reused_container(
+    install_script: InstallScript,
+) -> None:
+    """Test that the password of the reused container is checked over TCP."""
+    install_script.stub('docker')
+    install_script.stub('timeout')
+
+    result = install_script.run(f'{DATABASE_PORT}; run_postgres_container')
+
+    assert result.returncode == 0
+    assert any(
+        'psql -h 127.0.0.1' in call for call in install_script.calls('docker')
+    )
+    assert 'accepts the credentials of' in result.stdout
+
+
+def test_run_postgres_container_rejected_credentials(
+    install_script: InstallScript,
+) -> None:
+    """Test that a reused container with other credentials stops the script."""
+    install_script.stub(
+        'docker', 'if [[ "$*" == *PGPASSWORD* ]]; then exit 2; fi'
+    )
+    install_script.stub('timeout')
+
+    result = install_script.run(f'{DATABASE_PORT}; run_postgres_container')
+
+    assert result.returncode == 1
+    assert 'does not accept the credentials of' in result.stdout
+    assert 'remove the container to recreate it' in result.stdout
+
+
+def test_run_postgres_container_without_docker(
+    install_script: InstallScript,
+) -> None:
+    """Test that a missing Docker is reported before running anything."""
+    result = install_script.run(
+        f'{DATABASE_PORT}; run_postgres_container',
+        env={'PATH': str(install_script.bin_dir)},
+    )
+
+    assert result.returncode == 1
+    assert 'STOP: Docker is not installed' in result.stdout
+
+
+def test_create_database_creates_missing_database(
+    install_script: InstallScript,
+) -> None:
+    """Test that the database is created when it does not exist."""
+    install_script.stub('docker')
+
+    result = install_script.run('create_database')
+
+    assert result.returncode == 0
+    assert any(
+        'CREATE DATABASE openvair' in call
+        for call in install_script.calls('docker')
+    )
+
+
+def test_create_database_skips_existing_database(
+    install_script: InstallScript,
+) -> None:
+    """Test that an existing database is not created again."""
+    install_script.stub('docker', 'echo 1')
+
+    result = install_script.run('create_database')
+
+    assert result.returncode == 0
+    assert 'Database openvair already exists' in result.stdout
+    assert not any(
+        'CREATE DATABASE' in call for call in install_script.calls('docker')
+    )
+
+
+def test_wait_for_postgres_ready(install_script: InstallScript) -> None:
+    """Test that a ready database is reported without waiting."""
+    install_script.stub('docker')
+    install_script.stub('timeout')
+
+    result = install_script.run(f'{DATABASE_PORT}; wait_for_postgres')
+
+    assert result.returncode == 0
+    assert 'accepting connections at 0.0.0.0:5432' in result.stdout
+    assert install_script.calls('docker') == [
+        'exec postgres pg_isready -q -U aero'
+    ]
+    assert install_script.calls('timeout') == [
+        '5 bash -c </dev/tcp/0.0.0.0/5432'
+    ]
+
+
+def test_wait_for_postgres_polls_until_ready(
+    install_script: InstallScript,
+) -> None:
+    """Test that pg_isready is polled until the server starts."""
+    install_script.stub(
+        'docker',
+        f'[ $(grep -c "^docker .*pg_isready" "{install_script.calls_file}") '
+        f'-gt {FAILED_POLLS} ]',
+    )
+    install_script.stub('timeout')
+
+    result = install_script.run(f'{DATABASE_PORT}; wait_for_postgres')
+
+    assert result.returncode == 0
+    assert result.stdout.count('Waiting for PostgreSQL') == FAILED_POLLS
+
+
+def test_wait_for_postgres_unreachable(install_script: InstallScript) -> None:
+    """Test that an unreachable database stops with its address and log."""
+    install_script.stub(
+        'docker',
+        'if [ "$1" == "logs" ]; then echo "FATAL: could not bind"; fi',
+    )
+    install_script.stub('timeout', 'exit 1')
+
+    result = install_script.run(f'{DATABASE_PORT}; wait_for_postgres')
+
+    assert result.returncode == 1
+    assert 'logs --tail 20 postgres' in install_script.calls('docker')
+    assert 'FATAL: could not bind' in result.stdout
+    assert (
+        'STOP: Cannot reach PostgreSQL at 0.0.0.0:5432 after 60s'
+        in result.stdout
+    )
@@ -0,0 +1,174 @@
+"""Tests for the RabbitMQ container functions from install.sh.
+
+This test suite covers:
+- Skipping the broker when another messaging transport is configured.
+- Creating the container with the configured credentials, or starting an
+  existing one instead.
+- Reconciling the user and permissions of a reused container.
+- Waiting until the broker is running and accepts connections.
+"""
+
+import pytest
+
+from openvair.tests.install.utils import InstallScript
+
+FAILED_POLLS = 2
+RABBITMQ_ADDRESS = '127.0.0.1 5672'
+
+
+def test_create_rabbitmq_container_skipped_for_other_transport(
+    install_script: InstallScript,
+) -> None:
+    """Test that no container is created for a non-RabbitMQ transport."""
+    install_script.set_config_value('messaging', 'transport', "'kafka'")
+    install_script.stub('docker')
+
+    result = install_script.run('create_rabbitmq_container')
+
+    assert result.returncode == 0
+    assert 'skipping RabbitMQ container' in result.stdout
+    assert install_script.calls('docker') == []
+
+
+def test_create_rabbitmq_container_creates_container(
+    install_script: InstallScript,
+) -> None:
+    """Test that a missing container is created from [rabbitmq]."""
+    install_script.set_config_value('rabbitmq', 'user', "'openvair'")
+    install_script.stub(
+        'docker', 'if [ "$1" == "container" ]; then exit 1; fi'
+    )
+    install_script.stub('timeout')
+
+    result = install_script.run('create_rabbitmq_container')
+
+    assert result.returncode == 0
+    assert not any(
+        'rabbitmqctl add_user' in call
+        for call in install_script.calls('docker')
+    )
+    run_calls = [
+        call
+        for call in install_script.calls('docker')
+        if call.startswith('run ')
+    ]
+    assert len(run_calls) == 1
+    assert '-e RABBITMQ_DEFAULT_USER=openvair' in run_calls[0]
+    assert '-p 127.0.0.1:5672:5672' in run_calls[0]
+    assert (
+        'RabbitMQ is accepting connections at 127.0.0.1:5672' in result.stdout
+    )
+
+
+def test_create_rabbitmq_container_reuses_container(
+    install_script: InstallScript,
+) -> None:
+    """Test that an existing container is started instead of recreated."""
+    install_script.stub('docker')
+    install_script.stub('timeout')
+
+    result = install_script.run('create_rabbitmq_container')
+
+    assert result.returncode == 0
+    assert 'already exists, reusing it' in result.stdout
+    docker_calls = install_script.calls('docker')
+    assert 'start rabbit' in docker_calls
+    assert not any(call.startswith('run ') for call in docker_calls)
+
+
+@pytest.mark.parametrize(
+    ('users', 'expected'),
+    [
+        ('guest\t[administrator]', 'rabbitmqctl add_user openvair'),
+        ('openvair\t[]', 'rabbitmqctl change_password openvair'),
+    ],
+)
+def test_create_rabbitmq_container_reconciles_user(
+    install_script: InstallScript,
+    users: str,
+    expected: str,
+) -> None:
+    """Test that the configured user of a reused container is updated."""
+    install_script.set_config_value('rabbitmq', 'user', "'openvair'")
+    install_script.set_config_value('rabbitmq', 'password', "'s3cret'")
+    install_script.stub(
+        'docker',
+        f'if [[ "$*" == *list_users* ]]; then printf "{users}\\n"; fi',
+    )
+    install_script.stub('timeout')
+
+    result = install_script.run('create_rabbitmq_container')
+
+    assert result.returncode == 0
+    docker_calls = install_script.calls('docker')
+    assert f'exec rabbit {expected} s3cret' in docker_calls
+    assert (
+        'exec rabbit rabbitmqctl set_permissions -p / openvair .* .* .*'
+        in docker_calls
+    )
+    assert 'RabbitMQ user openvair is configured' in result.stdout
+    assert 's3cret' not in result.stdout
+    assert 's3cret' not in install_script.log_file.read_text()
+
+
+def test_create_rabbitmq_container_user_failure(
+    install_script: InstallScript,
+) -> None:
+    """Test that a failed user update stops the script."""
+    install_script.stub(
+        'docker',
+        'case "$*" in\n'
+        '    *list_users*) printf "guest\\t[administrator]\\n" ;;\n'
+        '    *change_password*) exit 1 ;;\n'
+        'esac',
+    )
+    install_script.stub('timeout')
+
+    result = install_script.run('create_rabbitmq_container')
+
+    assert result.returncode == 1
+    assert (
+        'STOP: Failed to update the password of RabbitMQ user guest'
+        in result.stdout
+    )
+
+
+def test_wait_for_rabbitmq_polls_until_running(
+    install_script: InstallScript,
+) -> None:
+    """Test that rabbitmqctl status is polled until the broker starts."""
+    install_script.stub(
+        'docker',
+        '[ $(grep -c "^docker .*rabbitmqctl status" '
+        f'"{install_script.calls_file}") -gt {FAILED_POLLS} ]',
+    )
+    install_script.stub('timeout')
+
+    result = install_script.run(f'wait_for_rabbitmq {RABBITMQ_ADDRESS}')
+
+    assert result.returncode == 0
+    assert result.stdout.count('Waiting for RabbitMQ') == FAILED_POLLS
+    assert install_script.calls('timeout') == [
+        '5 bash -c </dev/tcp/127.0.0.1/5672'
+    ]
+
+
+def test_wait_for_rabbitmq_port_unreachable(
+    install_script: InstallScript,
+) -> None:
+    """Test that a running broker without an open port stops with its log."""
+    install_script.stub(
+        'docker',
+        'if [ "$1" == "logs" ]; then echo "error: port in use"; fi',
+    )
+    install_script.stub('timeout', 'exit 1')
+
+    result = install_script.run(f'wait_for_rabbitmq {RABBITMQ_ADDRESS}')
+
+    assert result.returncode == 1
+    assert 'logs --tail 20 rabbit' in install_script.calls('docker')
+    assert 'error: port in use' in result.stdout
+    assert (
+        'STOP: Cannot reach RabbitMQ in container rabbit at 127.0.0.1:5672 '
+        'after 120s' in result.stdout
+    )
@@ -0,0 +1,73 @@
+"""Tests for verify_release_checksum from install.sh.
+
+This test suite covers:
+- Accepting an archive that matches the published sha256sums.txt.
+- Stopping on a checksum mismatch or a missing checksum.
+"""
+
+import hashlib
+
+from openvair.tests.install.utils import InstallScript
+
+ARCHIVE = 'node_exporter-1.5.0.linux-amd64.tar.gz'
+RELEASE_URL = 'https://example.com/releases/v1.5.0'
+
+
+def publish_checksums(install_script: InstallScript, checksums: str) -> None:
+    """Creates the archive and stubs curl to download the checksums file."""
+    (install_script.work_dir / ARCHIVE).write_bytes(b'node_exporter')
+    install_script.stub(
+        'curl',
+        'while [ $# -gt 0 ]; do\n'
+        '    if [ "$1" == "-o" ]; then output="$2"; fi\n'
+        '    shift\n'
+        'done\n'
+        f"printf '{checksums}' > \"$output\"",
+    )
+
+
+def verify(install_script: InstallScript) -> str:
+    """Runs verify_release_checksum in the download directory."""
+    return install_script.run(
+        f'cd "{install_script.work_dir}" && '
+        f'verify_release_checksum "{RELEASE_URL}" "{ARCHIVE}"'
+    ).stdout
+
+
+def test_verify_release_checksum_match(install_script: InstallScript) -> None:
+    """Test that an archive matching its published checksum is accepted."""
+    digest = hashlib.sha256(b'node_exporter').hexdigest()
+    publish_checksums(
+        install_script,
+        f'{"0" * 64}  other.tar.gz\\n{digest}  {ARCHIVE}\\n',
+    )
+
+    output = verify(install_script)
+
+    assert f'Checksum of {ARCHIVE} verified' in output
+    assert install_script.calls('curl') == [
+        f'-fsSL -o {ARCHIVE}.sha256sums {RELEASE_URL}/sha256sums.txt'
+    ]
+    assert not (install_script.work_dir / f'{ARCHIVE}.sha256sums').exists()
+
+
+def test_verify_release_checksum_mismatch(
+    install_script: InstallScript,
+) -> None:
+    """Test that a corrupted archive stops the script."""
+    publish_checksums(install_script, f'{"0" * 64}  {ARCHIVE}\\n')
+
+    output = verify(install_script)
+
+    assert f'STOP: Checksum mismatch for {ARCHIVE}' in output
+
+
+def test_verify_release_checksum_missing(
+    install_script: InstallScript,
+) -> None:
+    """Test that an archive absent from sha256sums.txt stops the script."""
+    publish_checksums(install_script, f'{"0" * 64}  other.tar.gz\\n')
+
+    output = verify(install_script)
+
+    assert f'STOP: No checksum for {ARCHIVE}' in output
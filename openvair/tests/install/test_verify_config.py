@@ -0,0 +1,133 @@
+"""Tests for get_config_value and verify_config from install.sh.
+
+This test suite covers:
+- Reading quoted, unquoted and commented values from project_config.toml.
+- Accepting the shipped project_config.toml.
+- Rejecting invalid ports, hosts, JWT settings, Sentry DSN and backuper.
+- Reporting all problems at once.
+"""
+
+import pytest
+
+from openvair.tests.install.utils import InstallScript
+
+
+@pytest.mark.parametrize(
+    ('value', 'expected'),
+    [
+        ("'localhost'", 'localhost'),
+        ('"localhost"', 'localhost'),
+        ("'local#host'  # comment", 'local#host'),
+        ('localhost  # comment', 'localhost'),
+        ("''", ''),
+    ],
+)
+def test_get_config_value(
+    install_script: InstallScript,
+    value: str,
+    expected: str,
+) -> None:
+    """Test that quotes and trailing comments are removed from a value."""
+    install_script.set_config_value('web_app', 'host', value)
+
+    result = install_script.run('get_config_value web_app host')
+
+    assert result.stdout == f'{expected}\n'
+
+
+def test_get_config_value_nested_section(
+    install_script: InstallScript,
+) -> None:
+    """Test that a key of an indented nested section is read."""
+    result = install_script.run(
+        'get_config_value notifications.email smtp_port'
+    )
+
+    assert result.stdout == '465\n'
+
+
+def test_verify_config_accepts_shipped_config(
+    install_script: InstallScript,
+) -> None:
+    """Test that the project_config.toml from the repository is valid."""
+    result = install_script.run('verify_config')
+
+    assert result.returncode == 0
+    assert 'Config is valid' in result.stdout
+
+
+@pytest.mark.parametrize(
+    ('section', 'key', 'value', 'expected'),
+    [
+        ('database', 'port', '0', 'database.port must be a port number'),
+        ('rabbitmq', 'port', '65536', 'rabbitmq.port must be a port number'),
+        ('web_app', 'port', "'http'", 'web_app.port must be a port number'),
+        (
+            'notifications.email',
+            'smtp_port',
+            '0',
+            'notifications.email.smtp_port must be a port number',
+        ),
+        (
+            'prometheus',
+            'port',
+            '8000',
+            'prometheus.port has the same value 8000 as web_app.port',
+        ),
+        ('rabbitmq', 'host', "''", 'rabbitmq.host must be an IP address'),
+        ('web_app', 'host', "'my host'", 'web_app.host must be an IP address'),
+        (
+            'jwt',
+            'access_token_expiration_minutes',
+            '0',
+            'jwt.access_token_expiration_minutes must be a positive number',
+        ),
+        ('jwt', 'algorithm', "'RS256'", 'jwt.algorithm must be one of'),
+        ('sentry', 'dsn', "'sentry.io'", 'sentry.dsn must be empty or a DSN'),
+        ('backup', 'backuper', "'borg'", 'backup.backuper must be one of'),
+    ],
+)
+def test_verify_config_rejects_invalid_value(
+    install_script: InstallScript,
+    section: str,
+    key: str,
+    value: str,
+    expected: str,
+) -> None:
+    """Test that an invalid value is reported with its key path."""
+    install_script.set_config_value(section, key, value)
+
+    result = install_script.run('verify_config')
+
+    assert result.returncode == 1
+    assert expected in result.stdout
+    assert 'STOP: Config verification failed with 1 error(s)' in result.stdout
+
+
+def test_verify_config_accepts_sentry_dsn(
+    install_script: InstallScript,
+) -> None:
+    """Test that a well-formed Sentry DSN is accepted."""
+    install_script.set_config_value(
+        'sentry', 'dsn', "'https://key@o1.ingest.sentry.io/42'"
+    )
+
+    result = install_script.run('verify_config')
+
+    assert result.returncode == 0
+
+
+def test_verify_config_reports_all_errors(
+    install_script: InstallScript,
+) -> None:
+    """Test that every invalid value is reported before stopping."""
+    install_script.set_config_value('database', 'port', '0')
+    install_script.set_config_value('rabbitmq', 'host', "''")
+    install_script.set_config_value('jwt', 'algorithm', "'none'")
+
+    result = install_script.run('verify_config')
+
+    assert 'database.port must be a port number' in result.stdout
+    assert 'rabbitmq.host must be an IP address' in result.stdout
+    assert 'jwt.algorithm must be one of' in result.stdout
+    assert 'STOP: Config verification failed with 3 error(s)' in result.stdout
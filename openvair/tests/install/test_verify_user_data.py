@@ -0,0 +1,102 @@
+"""Tests for verify_user_data from install.sh.
+
+This test suite covers:
+- Accepting valid credentials.
+- Each login rule: characters, leading digit, reserved names and length.
+- Each password rule: length and inequality to the login.
+
+The tests run in a UTF-8 locale, as the installation does. There lengths
+are counted in characters, and the login pattern has to reject non-latin
+letters by itself.
+"""
+
+from typing import Tuple
+
+import pytest
+
+from openvair.tests.install.utils import InstallScript
+
+UTF8_LOCALE = {'LC_ALL': 'C.UTF-8'}
+
+
+def verify(
+    install_script: InstallScript,
+    login: str,
+    password: str,
+) -> Tuple[int, str]:
+    """Runs verify_user_data with the given credentials."""
+    install_script.set_config_value('default_user', 'login', f"'{login}'")
+    install_script.set_config_value(
+        'default_user', 'password', f"'{password}'"
+    )
+    result = install_script.run('verify_user_data', env=UTF8_LOCALE)
+    return result.returncode, result.stdout
+
+
+@pytest.mark.parametrize('login', ['operator', 'op_1.a-b', 'Oper8'])
+def test_verify_user_data_valid(
+    install_script: InstallScript,
+    login: str,
+) -> None:
+    """Test that valid credentials pass."""
+    returncode, stdout = verify(install_script, login, 'secret!')
+
+    assert returncode == 0
+    assert 'User login is valid' in stdout
+    assert 'User password is valid' in stdout
+
+
+@pytest.mark.parametrize(
+    ('login', 'message'),
+    [
+        ('oper ator', 'may contain only latin letters'),
+        (' operator', 'may contain only latin letters'),
+        ('опера', 'may contain only latin letters'),
+        ('oper\tator', 'may contain only latin letters'),
+        ('1operator', 'must not start with a digit'),
+        ('admin', "User login 'admin' is reserved"),
+        ('postgres', "User login 'postgres' is reserved"),
+        ('', 'User login is too short'),
+        ('o' * 31, 'User login is not valid or not specified'),
+    ],
+)
+def test_verify_user_data_invalid_login(
+    install_script: InstallScript,
+    login: str,
+    message: str,
+) -> None:
+    """Test that each login rule reports which rule failed."""
+    returncode, stdout = verify(install_script, login, 'secret!')
+
+    assert returncode == 1
+    assert message in stdout
+
+
+@pytest.mark.parametrize(
+    ('password', 'message'),
+    [
+        ('пар', 'User password is too short'),
+        ('abc', 'User password is too short'),
+        ('operator', 'User password must not be equal to the user login'),
+    ],
+)
+def test_verify_user_data_invalid_password(
+    install_script: InstallScript,
+    password: str,
+    message: str,
+) -> None:
+    """Test that each password rule reports which rule failed."""
+    returncode, stdout = verify(install_script, 'operator', password)
+
+    assert returncode == 1
+    assert message in stdout
+
+
+def test_verify_user_data_counts_characters(
+    install_script: InstallScript,
+) -> None:
+    """Test that a non-latin password is measured in characters."""
+    returncode, stdout = verify(install_script, 'operator', 'пароль')
+
+    assert returncode == 0
+    assert 'User password is valid' in stdout
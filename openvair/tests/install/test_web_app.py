@@ -0,0 +1,66 @@
+"""Tests for the final web app restart and summary from install.sh.
+
+This test suite covers:
+- Waiting until the restarted web app accepts connections.
+- Stopping with the end of its journal when it does not come up.
+- The addresses printed in the final message.
+"""
+
+from openvair.tests.install.utils import InstallScript
+
+FAILED_POLLS = 2
+
+
+def test_restart_web_app_waits_for_port(
+    install_script: InstallScript,
+) -> None:
+    """Test that the web app port is polled until it accepts connections."""
+    install_script.stub('systemctl')
+    install_script.stub(
+        'timeout',
+        f'[ $(grep -c "^timeout " "{install_script.calls_file}") '
+        f'-gt {FAILED_POLLS} ]',
+    )
+
+    result = install_script.run('restart_web_app')
+
+    assert result.returncode == 0
+    assert install_script.calls('systemctl') == ['restart web-app.service']
+    assert result.stdout.count('Waiting for web-app.service') == FAILED_POLLS
+    assert (
+        'web-app.service is accepting connections at localhost:8000'
+        in result.stdout
+    )
+
+
+def test_restart_web_app_timeout(install_script: InstallScript) -> None:
+    """Test that a web app that never comes up stops with its journal."""
+    install_script.stub('systemctl')
+    install_script.stub('timeout', 'exit 1')
+    install_script.stub('journalctl', 'echo "web-app: Address in use"')
+
+    result = install_script.run('restart_web_app')
+
+    assert result.returncode == 1
+    assert install_script.calls('journalctl') == [
+        '-u web-app.service -n 20 --no-pager'
+    ]
+    assert 'web-app: Address in use' in result.stdout
+    assert (
+        'STOP: web-app.service is not accepting connections at '
+        'localhost:8000 after 60s' in result.stdout
+    )
+
+
+def test_print_final_message_addresses(install_script: InstallScript) -> None:
+    """Test that the web app, docs and Prometheus addresses are printed."""
+    install_script.stub('tput', 'echo 80')
+
+    result = install_script.run(
+        f'PROJECT_PATH="{install_script.work_dir}"; print_final_message'
+    )
+
+    assert result.returncode == 0
+    assert 'ADDRESS  https://localhost:8000' in result.stdout
+    assert 'DOCS     https://localhost:8000/docs/' in result.stdout
+    assert 'METRICS  https://localhost:9090' in result.stdout
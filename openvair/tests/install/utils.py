@@ -0,0 +1,150 @@
+"""Helpers for testing the functions of install.sh.
+
+install.sh is sourced in a bash subprocess, which loads its functions and
+variables without running the installation. System commands called by the
+functions (sudo, apt-get, docker, ...) are replaced by stub scripts on PATH
+that record their arguments, so the tests run without root and do not touch
+the system.
+"""
+
+import os
+import shutil
+import subprocess
+from typing import Dict, List, Optional
+from pathlib import Path
+
+PROJECT_PATH = Path(__file__).parents[3]
+INSTALL_SCRIPT = PROJECT_PATH / 'install.sh'
+PROJECT_CONFIG_FILE = PROJECT_PATH / 'project_config.toml'
+BASH = '/usr/bin/bash'
+
+
+class InstallScript:
+    """Runs install.sh functions against a temporary project directory.
+
+    Attributes:
+        work_dir (Path): Temporary directory for the test files.
+        bin_dir (Path): Directory with the command stubs, first on PATH.
+        calls_file (Path): File where the stubs record their calls.
+        config_file (Path): Copy of project_config.toml used by the functions.
+        log_file (Path): install.log written by the functions.
+    """
+
+    def __init__(self, work_dir: Path) -> None:
+        """Creates the stub directory and a copy of project_config.toml.
+
+        Args:
+            work_dir (Path): Temporary directory for the test files.
+        """
+        self.work_dir = work_dir
+        self.bin_dir = work_dir / 'bin'
+        self.calls_file = work_dir / 'calls'
+        self.config_file = work_dir / 'project_config.toml'
+        self.log_file = work_dir / 'install.log'
+        self.bin_dir.mkdir()
+        shutil.copy(PROJECT_CONFIG_FILE, self.config_file)
+        self.stub('sudo', 'exec "$@"')
+
+    def stub(self, command: str, body: str = '') -> None:
+        """Replaces a command on PATH with a bash script.
+
+        Every call is recorded before the body runs, see `calls`.
+
+        Args:
+            command (str): Name of the replaced command.
+            body (str): Bash code run by the stub, "$@" holds its arguments.
+        """
+        stub_file = self.bin_dir / command
+        stub_file.write_text(
+            '#!/usr/bin/bash\n'
+            f'echo "{command} $*" >> "{self.calls_file}"\n'
+            f'{body}\n'
+        )
+        stub_file.chmod(0o755)
+
+    def set_config_value(self, section: str, key: str, value: str) -> None:
+        """Replaces a value in the test copy of project_config.toml.
+
+        Args:
+            section (str): Section name, e.g. 'database' or 'backup.restic'.
+            key (str): Key in the section.
+            value (str): New value as written in TOML, e.g. "'localhost'".
+
+        Raises:
+            KeyError: If the section has no such key.
+        """
+        lines = self.config_file.read_text().splitlines()
+        current_section = None
+        for index, line in enumerate(lines):
+            stripped = line.strip()
+            if stripped.startswith('['):
+                current_section = stripped.strip('[]')
+            elif current_section == section and stripped.startswith(
+                f'{key} ='
+            ):
+                indent = line[: len(line) - len(line.lstrip())]
+                lines[index] = f'{indent}{key} = {value}'
+                self.config_file.write_text('\n'.join(lines) + '\n')
+                return
+
+        message = f'No {key} in [{section}] of {self.config_file}'
+        raise KeyError(message)
+
+    def calls(self, command: str) -> List[str]:
+        """Returns the arguments of every call of a stubbed command.
+
+        Args:
+            command (str): Name of the stubbed command.
+
+        Returns:
+            List[str]: Space separated arguments of each call, in call order.
+        """
+        if not self.calls_file.exists():
+            return []
+
+        prefix = f'{command} '
+        return [
+            line[len(prefix) :]
+            for line in self.calls_file.read_text().splitlines()
+            if line.startswith(prefix)
+        ]
+
+    def run(
+        self,
+        commands: str,
+        env: Optional[Dict[str, str]] = None,
+    ) -> 'subprocess.CompletedProcess[str]':
+        """Sources install.sh and runs bash commands in the same shell.
+
+        stop_script prints "STOP: <message>" and exits instead of waiting for
+        a key press, and sleep returns at once.
+
+        Args:
+            commands (str): Bash code calling install.sh functions.
+            env (Optional[Dict[str, str]]): Extra environment variables.
+
+        Returns:
+            subprocess.CompletedProcess[str]: Exit code and output of bash.
+        """
+        script = '\n'.join(
+            (
+                f'source "{INSTALL_SCRIPT}" 2> /dev/null',
+                f'PROJECT_CONFIG_FILE="{self.config_file}"',
+                f'LOG_FILE="{self.log_file}"',
+                'stop_script() { echo "STOP: $1"; exit 1; }',
+                'sleep() { :; }',
+                commands,
+            )
+        )
+        run_env = {
+            **os.environ,
+            'PATH': f'{self.bin_dir}:{os.environ["PATH"]}',
+            **(env or {}),
+        }
+        return subprocess.run(  # noqa: S603
+            [BASH, '-c', script],
+            capture_output=True,
+            text=True,
+            env=run_env,
+            check=False,
+        )